@@ -1,8 +1,18 @@
-use x86_64::structures::paging::{PageTable, PageTableFlags, RecursivePageTable,
-    PhysFrame, Page, Mapper, Size4KB, MapperFlush, 
+use x86_64::structures::paging::{PageTable, PageTableEntry, PageTableFlags, RecursivePageTable,
+    PhysFrame, Page, PageRange, PageRangeInclusive, PageSize, Mapper, Size4KB, Size2MB, Size1GB, MapperFlush,
     MapToError, UnmapError, FlagUpdateError};
+use x86_64::{VirtAddr, PhysAddr};
 use x86_64::ux::u9;
+use x86_64::instructions::tlb;
+use x86_64::registers::control::Cr3;
 
+// Huge-page support below allocates intermediate P3/P2 table frames
+// through the same `memory::allocate_frame`/`FrameAllocator::allocate_frame`
+// used for 4 KiB pages (a page-table frame is always 4 KiB, regardless of
+// the size it maps), but the *mapped* 2 MiB/1 GiB frames themselves come
+// from `memory::allocate_frame_2mb`/`_1gb` and
+// `FrameAllocator::allocate_frame_2mb`/`_1gb`, which this module expects
+// `arch::memory` to provide alongside the existing 4 KiB ones.
 use arch::lock::{IrqLock, IrqGuard};
 use arch::memory;
 use core::cell::UnsafeCell;
@@ -11,6 +21,109 @@ const P4: *mut PageTable = 0xffffffff_fffff000 as *mut _;
 const RECURSIVE_PAGE_INDEX: u9 = u9::MAX;
 static PAGE_TABLE_LOCK: IrqLock<Option<RecursivePageTable>> = IrqLock::new(None);
 
+/// Address of the P3 table covering `page`, reached by walking one level
+/// short of a full 4-level recursive walk.
+fn p3_table_ptr<S: PageSize>(page: Page<S>) -> *mut PageTable {
+    (0xffffffff_ffe00000 | (u64::from(page.p4_index()) << 12)) as *mut PageTable
+}
+
+/// Address of the P2 table covering `page`, reached by walking two levels
+/// short of a full 4-level recursive walk.
+fn p2_table_ptr<S: PageSize>(page: Page<S>) -> *mut PageTable {
+    (0xffffffff_c0000000 | (u64::from(page.p4_index()) << 21) | (u64::from(page.p3_index()) << 12)) as *mut PageTable
+}
+
+/// Address of the P1 table covering `page`, reached by a full 4-level
+/// recursive walk.
+fn p1_table_ptr(page: Page<Size4KB>) -> *mut PageTable {
+    (0xffffff80_00000000
+        | (u64::from(page.p4_index()) << 30)
+        | (u64::from(page.p3_index()) << 21)
+        | (u64::from(page.p2_index()) << 12)) as *mut PageTable
+}
+
+/// Writes a huge-page mapping directly into a P3 (1 GiB) or P2 (2 MiB)
+/// entry, bypassing the walk down to P1 that a [`Size4KB`] mapping needs.
+/// `index` is the index of the entry to set within the table pointed to
+/// by `table`.
+unsafe fn set_huge_entry(table: *mut PageTable, index: usize, frame: PhysFrame, flags: PageTableFlags) {
+    (*table)[index].set_addr(frame.start_address(), flags | PageTableFlags::HUGE_PAGE);
+}
+
+/// The P3 entry covering `page`, or `None` if the P4 entry above it is
+/// absent — meaning `page`'s address is unmapped before the walk even
+/// reaches P3, so `p3_table_ptr(page)` is not safe to dereference.
+fn p3_entry_ptr<S: PageSize>(page: Page<S>) -> Option<*mut PageTableEntry> {
+    let p4 = unsafe { &*P4 };
+    if p4[usize::from(page.p4_index())].is_unused() {
+        return None;
+    }
+    Some(unsafe { &mut (*p3_table_ptr(page))[usize::from(page.p3_index())] as *mut PageTableEntry })
+}
+
+/// The P2 entry covering `page`, or `None` if the P4/P3 levels above it
+/// are absent, or P3 is itself a 1 GiB huge-page mapping (so there is no
+/// P2 table to descend into and `p2_table_ptr(page)` is not safe to
+/// dereference).
+fn p2_entry_ptr<S: PageSize>(page: Page<S>) -> Option<*mut PageTableEntry> {
+    let p3_entry = unsafe { &*p3_entry_ptr(page)? };
+    if p3_entry.is_unused() || p3_entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+        return None;
+    }
+    Some(unsafe { &mut (*p2_table_ptr(page))[usize::from(page.p2_index())] as *mut PageTableEntry })
+}
+
+/// The P1 entry covering `page`, or `None` if any parent level is absent
+/// or is itself a huge-page mapping (so `p1_table_ptr(page)` is not safe
+/// to dereference).
+fn p1_entry_ptr(page: Page<Size4KB>) -> Option<*mut PageTableEntry> {
+    let p2_entry = unsafe { &*p2_entry_ptr(page)? };
+    if p2_entry.is_unused() || p2_entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+        return None;
+    }
+    Some(unsafe { &mut (*p1_table_ptr(page))[usize::from(page.p1_index())] as *mut PageTableEntry })
+}
+
+/// Ensures the P4 entry above `page` points at a present P3 table,
+/// allocating and zeroing a fresh one (via `allocate_frame`) if it didn't
+/// already. Needed before a 1 GiB mapping can write its P3 entry, or
+/// before descending further towards a 2 MiB/4 KiB mapping.
+fn ensure_p3_table<S: PageSize>(page: Page<S>, allocate_frame: &mut impl FnMut() -> Option<PhysFrame<Size4KB>>) -> Result<(), MapToError> {
+    let p4 = unsafe { &mut *P4 };
+    let entry = &mut p4[usize::from(page.p4_index())];
+
+    if entry.is_unused() {
+        let frame = allocate_frame().ok_or(MapToError::FrameAllocationFailed)?;
+        entry.set_frame(frame, PageTableFlags::PRESENT | PageTableFlags::WRITABLE);
+        unsafe { (&mut *p3_table_ptr(page)).zero() };
+    }
+
+    Ok(())
+}
+
+/// Ensures the P3 entry above `page` points at a present P2 table,
+/// allocating and zeroing a fresh one (via `allocate_frame`) if it didn't
+/// already, creating the P3 table itself first if necessary. Needed before
+/// a 2 MiB mapping can write its P2 entry.
+fn ensure_p2_table(page: Page<Size2MB>, allocate_frame: &mut impl FnMut() -> Option<PhysFrame<Size4KB>>) -> Result<(), MapToError> {
+    ensure_p3_table(page, allocate_frame)?;
+
+    let p3 = unsafe { &mut *p3_table_ptr(page) };
+    let entry = &mut p3[usize::from(page.p3_index())];
+
+    if entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+        return Err(MapToError::ParentEntryHugePage);
+    }
+
+    if entry.is_unused() {
+        let frame = allocate_frame().ok_or(MapToError::FrameAllocationFailed)?;
+        entry.set_frame(frame, PageTableFlags::PRESENT | PageTableFlags::WRITABLE);
+        unsafe { (&mut *p2_table_ptr(page)).zero() };
+    }
+
+    Ok(())
+}
+
 pub unsafe fn init() -> PageMapper {
     *PAGE_TABLE_LOCK.lock() = Some(RecursivePageTable::new_unchecked(&mut*P4, RECURSIVE_PAGE_INDEX));
     PageMapper::new()
@@ -52,7 +165,212 @@ impl PageMapper {
     pub fn translate(&mut self, page: Page<Size4KB>) -> Option<PhysFrame> {
         self.table.translate(page)
     }
-    
+
+    /// Maps `frame` to the identically-numbered virtual page — handy for
+    /// MMIO and other early device setup where the physical address must
+    /// also be the virtual one.
+    pub fn identity_map(&mut self, frame: PhysFrame<Size4KB>, flags: PageTableFlags) -> Result<MapperFlush<Size4KB>, MapToError> {
+        let page = Page::containing_address(VirtAddr::new(frame.start_address().as_u64()));
+        self.map_to(page, frame, flags)
+    }
+
+    /// Translates an arbitrary virtual address to its physical address,
+    /// walking to whichever level `addr` actually resolves at (a 1 GiB, 2
+    /// MiB or 4 KiB entry) and adding the intra-page offset back onto the
+    /// resolved frame's base address.
+    pub fn translate_addr(&mut self, addr: VirtAddr) -> Option<PhysAddr> {
+        if let Some(frame) = self.translate_1gb(Page::containing_address(addr)) {
+            return Some(frame.start_address() + (addr.as_u64() % Size1GB::SIZE));
+        }
+        if let Some(frame) = self.translate_2mb(Page::containing_address(addr)) {
+            return Some(frame.start_address() + (addr.as_u64() % Size2MB::SIZE));
+        }
+
+        self.translate(Page::containing_address(addr))
+            .map(|frame| frame.start_address() + (addr.as_u64() % Size4KB::SIZE))
+    }
+
+    /// Maps `pages` writable frames descending from `top`, leaving the page
+    /// immediately below the lowest mapped page unmapped as a guard page.
+    /// `flags` is always combined with [`WRITABLE`](PageTableFlags::WRITABLE),
+    /// so the mapped pages are writable regardless of what's passed in.
+    ///
+    /// The guard page is never backed by a frame, so a thread that overruns
+    /// its stack faults on the unmapped page instead of silently corrupting
+    /// whatever sits below it. Returns the mapped range together with the
+    /// guard page so the caller can record it for its page-fault handler.
+    ///
+    /// If a page in the middle of the stack fails to map, every page mapped
+    /// so far is unmapped again before the error is returned, so a failed
+    /// call never leaves part of a stack mapped.
+    pub fn map_stack(&mut self, top: Page<Size4KB>, pages: u64, flags: PageTableFlags) -> Result<(PageRangeInclusive<Size4KB>, Page<Size4KB>), MapToError> {
+        assert!(pages > 0, "a stack must have at least one page");
+
+        let bottom = top - (pages - 1);
+        let flags = flags | PageTableFlags::WRITABLE;
+
+        for (mapped, page) in Page::range_inclusive(bottom, top).enumerate() {
+            if let Err(e) = self.map(page, flags) {
+                for rolled_back in Page::range_inclusive(bottom, top).take(mapped) {
+                    let _ = self.unmap(rolled_back);
+                }
+                return Err(e);
+            }
+        }
+
+        let guard_page = bottom - 1;
+
+        Ok((Page::range_inclusive(bottom, top), guard_page))
+    }
+
+    /// Maps a 2 MiB page, programming the P2 entry directly instead of
+    /// walking down to a P1 table. The frame is only allocated once the
+    /// target entry is confirmed free, so a [`ParentEntryHugePage`](MapToError::ParentEntryHugePage)
+    /// or [`PageAlreadyMapped`](MapToError::PageAlreadyMapped) never leaks it.
+    pub fn map_2mb(&mut self, page: Page<Size2MB>, flags: PageTableFlags) -> Result<MapperFlush<Size2MB>, MapToError> {
+        ensure_p2_table(page, &mut || memory::allocate_frame())?;
+
+        let p2 = p2_table_ptr(page);
+        let entry = unsafe { &mut (*p2)[usize::from(page.p2_index())] };
+        if !entry.is_unused() {
+            return Err(MapToError::PageAlreadyMapped);
+        }
+
+        let frame = memory::allocate_frame_2mb()
+            .expect("Couldn't allocate any 2 MiB frames!");
+        unsafe { set_huge_entry(p2, usize::from(page.p2_index()), PhysFrame::containing_address(frame.start_address()), flags) };
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Maps `page` to `frame`, programming the P2 entry directly instead of
+    /// walking down to a P1 table. Missing P3/P2 tables along the way are
+    /// allocated and zeroed first.
+    pub fn map_to_2mb(&mut self, page: Page<Size2MB>, frame: PhysFrame<Size2MB>, flags: PageTableFlags) -> Result<MapperFlush<Size2MB>, MapToError> {
+        ensure_p2_table(page, &mut || memory::allocate_frame())?;
+
+        let p2 = p2_table_ptr(page);
+        let entry = unsafe { &mut (*p2)[usize::from(page.p2_index())] };
+        if !entry.is_unused() {
+            return Err(MapToError::PageAlreadyMapped);
+        }
+
+        unsafe { set_huge_entry(p2, usize::from(page.p2_index()), PhysFrame::containing_address(frame.start_address()), flags) };
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Maps a 1 GiB page, programming the P3 entry directly instead of
+    /// walking down to a P1 table. The frame is only allocated once the
+    /// target entry is confirmed free, so missing-table allocation failure
+    /// or a [`PageAlreadyMapped`](MapToError::PageAlreadyMapped) never leaks it.
+    pub fn map_1gb(&mut self, page: Page<Size1GB>, flags: PageTableFlags) -> Result<MapperFlush<Size1GB>, MapToError> {
+        ensure_p3_table(page, &mut || memory::allocate_frame())?;
+
+        let p3 = p3_table_ptr(page);
+        let entry = unsafe { &mut (*p3)[usize::from(page.p3_index())] };
+        if !entry.is_unused() {
+            return Err(MapToError::PageAlreadyMapped);
+        }
+
+        let frame = memory::allocate_frame_1gb()
+            .expect("Couldn't allocate any 1 GiB frames!");
+        unsafe { set_huge_entry(p3, usize::from(page.p3_index()), PhysFrame::containing_address(frame.start_address()), flags) };
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Maps `page` to `frame`, programming the P3 entry directly instead of
+    /// walking down to a P1 table. A missing P3 table is allocated and
+    /// zeroed first.
+    pub fn map_to_1gb(&mut self, page: Page<Size1GB>, frame: PhysFrame<Size1GB>, flags: PageTableFlags) -> Result<MapperFlush<Size1GB>, MapToError> {
+        ensure_p3_table(page, &mut || memory::allocate_frame())?;
+
+        let p3 = p3_table_ptr(page);
+        let entry = unsafe { &mut (*p3)[usize::from(page.p3_index())] };
+        if !entry.is_unused() {
+            return Err(MapToError::PageAlreadyMapped);
+        }
+
+        unsafe { set_huge_entry(p3, usize::from(page.p3_index()), PhysFrame::containing_address(frame.start_address()), flags) };
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Unmaps a 2 MiB page previously mapped with [`map_2mb`](PageMapper::map_2mb).
+    pub fn unmap_2mb(&mut self, page: Page<Size2MB>) -> Result<MapperFlush<Size2MB>, UnmapError> {
+        let p2 = p2_table_ptr(page);
+        let entry = unsafe { &mut (*p2)[usize::from(page.p2_index())] };
+        if entry.is_unused() {
+            return Err(UnmapError::PageNotMapped);
+        }
+        let frame = PhysFrame::<Size2MB>::from_start_address(entry.addr()).map_err(|_| UnmapError::InvalidFrameAddress(entry.addr()))?;
+        memory::deallocate_frame_2mb(frame);
+        entry.set_unused();
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Unmaps a 1 GiB page previously mapped with [`map_1gb`](PageMapper::map_1gb).
+    pub fn unmap_1gb(&mut self, page: Page<Size1GB>) -> Result<MapperFlush<Size1GB>, UnmapError> {
+        let p3 = p3_table_ptr(page);
+        let entry = unsafe { &mut (*p3)[usize::from(page.p3_index())] };
+        if entry.is_unused() {
+            return Err(UnmapError::PageNotMapped);
+        }
+        let frame = PhysFrame::<Size1GB>::from_start_address(entry.addr()).map_err(|_| UnmapError::InvalidFrameAddress(entry.addr()))?;
+        memory::deallocate_frame_1gb(frame);
+        entry.set_unused();
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Updates the flags of an already-mapped 2 MiB page. Returns
+    /// [`PageNotMapped`](FlagUpdateError::PageNotMapped) if the P2 entry
+    /// isn't a huge-page mapping at all — it's a pointer to a P1 table, and
+    /// setting `HUGE_PAGE` on it would corrupt the hierarchy.
+    pub fn remap_2mb(&mut self, page: Page<Size2MB>, flags: PageTableFlags) -> Result<MapperFlush<Size2MB>, FlagUpdateError> {
+        let p2 = p2_table_ptr(page);
+        let entry = unsafe { &mut (*p2)[usize::from(page.p2_index())] };
+        if entry.is_unused() || !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return Err(FlagUpdateError::PageNotMapped);
+        }
+        entry.set_flags(flags | PageTableFlags::HUGE_PAGE);
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Updates the flags of an already-mapped 1 GiB page. Returns
+    /// [`PageNotMapped`](FlagUpdateError::PageNotMapped) if the P3 entry
+    /// isn't a huge-page mapping at all — it's a pointer to a P2 table, and
+    /// setting `HUGE_PAGE` on it would corrupt the hierarchy.
+    pub fn remap_1gb(&mut self, page: Page<Size1GB>, flags: PageTableFlags) -> Result<MapperFlush<Size1GB>, FlagUpdateError> {
+        let p3 = p3_table_ptr(page);
+        let entry = unsafe { &mut (*p3)[usize::from(page.p3_index())] };
+        if entry.is_unused() || !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return Err(FlagUpdateError::PageNotMapped);
+        }
+        entry.set_flags(flags | PageTableFlags::HUGE_PAGE);
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Translates a 2 MiB page to its backing frame. Returns `None` if any
+    /// level above P2 is absent (an unmapped address, which must not be
+    /// walked any further) or if the P2 entry is present but not a huge
+    /// page — a non-huge P2 entry points at a P1 table, not a data frame.
+    pub fn translate_2mb(&mut self, page: Page<Size2MB>) -> Option<PhysFrame<Size2MB>> {
+        let entry = unsafe { &*p2_entry_ptr(page)? };
+        if entry.is_unused() || !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return None;
+        }
+        PhysFrame::from_start_address(entry.addr()).ok()
+    }
+
+    /// Translates a 1 GiB page to its backing frame. Returns `None` if the
+    /// level above P3 is absent (an unmapped address, which must not be
+    /// walked any further) or if the P3 entry is present but not a huge
+    /// page — a non-huge P3 entry points at a P2 table, not a data frame.
+    pub fn translate_1gb(&mut self, page: Page<Size1GB>) -> Option<PhysFrame<Size1GB>> {
+        let entry = unsafe { &*p3_entry_ptr(page)? };
+        if entry.is_unused() || !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return None;
+        }
+        PhysFrame::from_start_address(entry.addr()).ok()
+    }
+
     /// For faster mapping of a group of frames
     pub fn lock<'table>(&'table mut self) -> LockedPageMapper<'table, 'static, impl memory::FrameAllocator> {
         let fa = memory::FRAME_ALLOCATOR.lock_map(|opt| opt.as_mut().unwrap());
@@ -61,6 +379,115 @@ impl PageMapper {
             allocator_guard: UnsafeCell::new(fa),
         }
     }
+
+    /// Temporarily points the recursive P4 slot at `inactive`'s table and
+    /// runs `f` with a mapper targeting that hierarchy instead of the
+    /// active one, restoring the original recursive slot afterwards.
+    ///
+    /// This is how a fresh address space built with [`InactivePageTable`]
+    /// gets populated before it is ever switched to: every `map`/`unmap`
+    /// call `f` makes goes through the recursive trick but lands in
+    /// `inactive`'s tables, not the currently active ones.
+    ///
+    /// Once the recursive slot is repointed, `P4` (and every `p3_table_ptr`/
+    /// `p2_table_ptr`/`p1_table_ptr` address, which all resolve through it)
+    /// stops reaching the active table at all — it reaches `inactive`'s
+    /// table instead, recursively. So the active table's own P4 frame is
+    /// saved up front from `Cr3::read()` and, to restore it afterwards,
+    /// mapped as an ordinary data page through `temporary_page` instead of
+    /// through the now-hijacked recursive address, exactly as
+    /// [`InactivePageTable::new`] does to edit a table that isn't active.
+    pub fn with_inactive(
+        &mut self,
+        inactive: &mut InactivePageTable,
+        temporary_page: &mut TemporaryPage,
+        f: impl FnOnce(&mut LockedPageMapper<impl memory::FrameAllocator>),
+    ) {
+        let recursive_index = usize::from(RECURSIVE_PAGE_INDEX);
+        let active_frame = Cr3::read().0;
+
+        let scratch = temporary_page.map(self, active_frame);
+        let original_entry = unsafe { (&*scratch)[recursive_index].clone() };
+
+        let p4 = unsafe { &mut *P4 };
+        p4[recursive_index].set_frame(inactive.p4_frame, PageTableFlags::PRESENT | PageTableFlags::WRITABLE);
+        tlb::flush_all();
+
+        f(&mut self.lock());
+
+        unsafe { (&mut *scratch)[recursive_index] = original_entry };
+        tlb::flush_all();
+
+        temporary_page.unmap(self);
+    }
+
+    /// Writes `new`'s P4 frame to CR3, making it the active address space,
+    /// and returns the table that was active beforehand so the caller can
+    /// switch back to it later.
+    pub fn switch(&mut self, new: InactivePageTable) -> InactivePageTable {
+        let (old_frame, flags) = Cr3::read();
+
+        unsafe { Cr3::write(new.p4_frame, flags) };
+
+        InactivePageTable { p4_frame: old_frame }
+    }
+}
+
+/// A scratch virtual page used to map an arbitrary physical frame so it can
+/// be read or written while it isn't part of the active address space —
+/// most notably, to zero and edit an [`InactivePageTable`]'s P4 frame
+/// before that table is ever switched to.
+pub struct TemporaryPage {
+    page: Page<Size4KB>,
+}
+
+impl TemporaryPage {
+    /// Reserves `page` as the scratch page. The page must not otherwise be
+    /// in use by the active address space.
+    pub const fn new(page: Page<Size4KB>) -> Self {
+        TemporaryPage { page }
+    }
+
+    /// Maps `frame` at the scratch page and returns a pointer to it.
+    pub fn map(&mut self, mapper: &mut PageMapper, frame: PhysFrame<Size4KB>) -> *mut PageTable {
+        let flags = PageTableFlags::PRESENT | PageTableFlags::WRITABLE;
+        mapper.map_to(self.page, frame, flags)
+            .expect("TemporaryPage::map: failed to map scratch page")
+            .flush();
+
+        self.page.start_address().as_mut_ptr()
+    }
+
+    /// Unmaps the scratch page.
+    pub fn unmap(&mut self, mapper: &mut PageMapper) {
+        mapper.unmap(self.page)
+            .expect("TemporaryPage::unmap: scratch page wasn't mapped")
+            .flush();
+    }
+}
+
+/// A freshly allocated address space that isn't active yet.
+///
+/// The P4 frame is zeroed and its own recursive entry is set up, so it can
+/// be populated through [`PageMapper::with_inactive`] and later made active
+/// with [`PageMapper::switch`] — the foundation for per-process isolation.
+pub struct InactivePageTable {
+    p4_frame: PhysFrame<Size4KB>,
+}
+
+impl InactivePageTable {
+    /// Zeroes `frame` and sets up its recursive P4 entry, using
+    /// `temporary_page` to reach it without making it the active table.
+    pub fn new(mapper: &mut PageMapper, temporary_page: &mut TemporaryPage, frame: PhysFrame<Size4KB>) -> Self {
+        {
+            let table = unsafe { &mut *temporary_page.map(mapper, frame) };
+            table.zero();
+            table[usize::from(RECURSIVE_PAGE_INDEX)].set_frame(frame, PageTableFlags::PRESENT | PageTableFlags::WRITABLE);
+        }
+        temporary_page.unmap(mapper);
+
+        InactivePageTable { p4_frame: frame }
+    }
 }
 
 pub struct LockedPageMapper<'table, 'allocator, FA: 'allocator + memory::FrameAllocator> {
@@ -98,11 +525,262 @@ impl<'table, 'allocator, FA: memory::FrameAllocator> LockedPageMapper<'table, 'a
         self.table().update_flags(page, flags)
     }
 
-    // pub fn swap(&mut self, x: Page<Size4KB>, y: Page<Size4KB>) -> Result<DoubleMapperFlush<Size4KB>, SwapPageError> {
-    //     self.table().swap(x, y)
-    // }
+    /// Exchanges the physical frames backing `x` and `y`, preserving each
+    /// page's own flags. Neither frame's contents are touched, so this is
+    /// useful for zero-copy buffer handoff and page migration/defragmentation.
+    pub fn swap(&mut self, x: Page<Size4KB>, y: Page<Size4KB>) -> Result<DoubleMapperFlush<Size4KB>, SwapPageError> {
+        let x_entry = p1_entry_ptr(x).ok_or(SwapPageError::NotMapped(x))?;
+        let y_entry = p1_entry_ptr(y).ok_or(SwapPageError::NotMapped(y))?;
+
+        let (x_frame, x_flags) = unsafe {
+            let entry = &*x_entry;
+            if !entry.flags().contains(PageTableFlags::PRESENT) {
+                return Err(SwapPageError::NotMapped(x));
+            }
+            (entry.frame().map_err(|_| SwapPageError::InvalidFrameAddress(entry.addr()))?, entry.flags())
+        };
+        let (y_frame, y_flags) = unsafe {
+            let entry = &*y_entry;
+            if !entry.flags().contains(PageTableFlags::PRESENT) {
+                return Err(SwapPageError::NotMapped(y));
+            }
+            (entry.frame().map_err(|_| SwapPageError::InvalidFrameAddress(entry.addr()))?, entry.flags())
+        };
+
+        unsafe {
+            (*x_entry).set_addr(y_frame.start_address(), x_flags);
+            (*y_entry).set_addr(x_frame.start_address(), y_flags);
+        }
+
+        Ok(DoubleMapperFlush { x, y })
+    }
 
     pub fn translate(&self, page: Page<Size4KB>) -> Option<PhysFrame> {
         self.table().translate(page)
     }
+
+    /// Maps a 2 MiB page using the already-locked frame allocator,
+    /// programming the P2 entry directly instead of walking down to a P1
+    /// table. Missing P3/P2 tables along the way are allocated and zeroed
+    /// first, also via the already-locked allocator. The huge frame itself
+    /// is only allocated once the target entry is confirmed free, so a
+    /// [`ParentEntryHugePage`](MapToError::ParentEntryHugePage) or
+    /// [`PageAlreadyMapped`](MapToError::PageAlreadyMapped) never leaks it.
+    pub fn map_2mb(&mut self, page: Page<Size2MB>, flags: PageTableFlags) -> Result<MapperFlush<Size2MB>, MapToError> {
+        ensure_p2_table(page, &mut || self.allocator().allocate_frame())?;
+
+        let p2 = p2_table_ptr(page);
+        let entry = unsafe { &mut (*p2)[usize::from(page.p2_index())] };
+        if !entry.is_unused() {
+            return Err(MapToError::PageAlreadyMapped);
+        }
+
+        let frame = self.allocator().allocate_frame_2mb()
+            .expect("Couldn't allocate any 2 MiB frames!");
+        unsafe { set_huge_entry(p2, usize::from(page.p2_index()), PhysFrame::containing_address(frame.start_address()), flags) };
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Maps a 1 GiB page using the already-locked frame allocator,
+    /// programming the P3 entry directly instead of walking down to a P1
+    /// table. A missing P3 table is allocated and zeroed first, also via
+    /// the already-locked allocator. The huge frame itself is only
+    /// allocated once the target entry is confirmed free, so missing-table
+    /// allocation failure or a [`PageAlreadyMapped`](MapToError::PageAlreadyMapped)
+    /// never leaks it.
+    pub fn map_1gb(&mut self, page: Page<Size1GB>, flags: PageTableFlags) -> Result<MapperFlush<Size1GB>, MapToError> {
+        ensure_p3_table(page, &mut || self.allocator().allocate_frame())?;
+
+        let p3 = p3_table_ptr(page);
+        let entry = unsafe { &mut (*p3)[usize::from(page.p3_index())] };
+        if !entry.is_unused() {
+            return Err(MapToError::PageAlreadyMapped);
+        }
+
+        let frame = self.allocator().allocate_frame_1gb()
+            .expect("Couldn't allocate any 1 GiB frames!");
+        unsafe { set_huge_entry(p3, usize::from(page.p3_index()), PhysFrame::containing_address(frame.start_address()), flags) };
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Unmaps a 2 MiB page, returning its frame to the already-locked allocator.
+    pub fn unmap_2mb(&mut self, page: Page<Size2MB>) -> Result<MapperFlush<Size2MB>, UnmapError> {
+        let p2 = p2_table_ptr(page);
+        let entry = unsafe { &mut (*p2)[usize::from(page.p2_index())] };
+        if entry.is_unused() {
+            return Err(UnmapError::PageNotMapped);
+        }
+        let frame = PhysFrame::<Size2MB>::from_start_address(entry.addr()).map_err(|_| UnmapError::InvalidFrameAddress(entry.addr()))?;
+        self.allocator().deallocate_frame_2mb(frame);
+        entry.set_unused();
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Unmaps a 1 GiB page, returning its frame to the already-locked allocator.
+    pub fn unmap_1gb(&mut self, page: Page<Size1GB>) -> Result<MapperFlush<Size1GB>, UnmapError> {
+        let p3 = p3_table_ptr(page);
+        let entry = unsafe { &mut (*p3)[usize::from(page.p3_index())] };
+        if entry.is_unused() {
+            return Err(UnmapError::PageNotMapped);
+        }
+        let frame = PhysFrame::<Size1GB>::from_start_address(entry.addr()).map_err(|_| UnmapError::InvalidFrameAddress(entry.addr()))?;
+        self.allocator().deallocate_frame_1gb(frame);
+        entry.set_unused();
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Updates the flags of an already-mapped 2 MiB page. Returns
+    /// [`PageNotMapped`](FlagUpdateError::PageNotMapped) if the P2 entry
+    /// isn't a huge-page mapping at all — it's a pointer to a P1 table, and
+    /// setting `HUGE_PAGE` on it would corrupt the hierarchy.
+    pub fn remap_2mb(&mut self, page: Page<Size2MB>, flags: PageTableFlags) -> Result<MapperFlush<Size2MB>, FlagUpdateError> {
+        let p2 = p2_table_ptr(page);
+        let entry = unsafe { &mut (*p2)[usize::from(page.p2_index())] };
+        if entry.is_unused() || !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return Err(FlagUpdateError::PageNotMapped);
+        }
+        entry.set_flags(flags | PageTableFlags::HUGE_PAGE);
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Updates the flags of an already-mapped 1 GiB page. Returns
+    /// [`PageNotMapped`](FlagUpdateError::PageNotMapped) if the P3 entry
+    /// isn't a huge-page mapping at all — it's a pointer to a P2 table, and
+    /// setting `HUGE_PAGE` on it would corrupt the hierarchy.
+    pub fn remap_1gb(&mut self, page: Page<Size1GB>, flags: PageTableFlags) -> Result<MapperFlush<Size1GB>, FlagUpdateError> {
+        let p3 = p3_table_ptr(page);
+        let entry = unsafe { &mut (*p3)[usize::from(page.p3_index())] };
+        if entry.is_unused() || !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return Err(FlagUpdateError::PageNotMapped);
+        }
+        entry.set_flags(flags | PageTableFlags::HUGE_PAGE);
+        Ok(MapperFlush::new(page))
+    }
+
+    /// Translates a 2 MiB page to its backing frame. Returns `None` if any
+    /// level above P2 is absent (an unmapped address, which must not be
+    /// walked any further) or if the P2 entry is present but not a huge
+    /// page — a non-huge P2 entry points at a P1 table, not a data frame.
+    pub fn translate_2mb(&self, page: Page<Size2MB>) -> Option<PhysFrame<Size2MB>> {
+        let entry = unsafe { &*p2_entry_ptr(page)? };
+        if entry.is_unused() || !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return None;
+        }
+        PhysFrame::from_start_address(entry.addr()).ok()
+    }
+
+    /// Translates a 1 GiB page to its backing frame. Returns `None` if the
+    /// level above P3 is absent (an unmapped address, which must not be
+    /// walked any further) or if the P3 entry is present but not a huge
+    /// page — a non-huge P3 entry points at a P2 table, not a data frame.
+    pub fn translate_1gb(&self, page: Page<Size1GB>) -> Option<PhysFrame<Size1GB>> {
+        let entry = unsafe { &*p3_entry_ptr(page)? };
+        if entry.is_unused() || !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return None;
+        }
+        PhysFrame::from_start_address(entry.addr()).ok()
+    }
+
+    /// Maps a contiguous range of pages, reusing the already-locked frame
+    /// allocator for every page instead of re-locking it per call.
+    ///
+    /// If a page in the middle of the range fails to map, every page
+    /// mapped so far is unmapped again before the error is returned, so a
+    /// failed call never leaves the address space half-mapped.
+    ///
+    /// The per-page TLB flush is deferred: flush the whole range once via
+    /// the returned [`MapperFlushRange`] rather than flushing after every page.
+    pub fn map_range(&mut self, pages: PageRange<Size4KB>, flags: PageTableFlags) -> Result<MapperFlushRange, MapToError> {
+        for (mapped, page) in pages.clone().enumerate() {
+            if let Err(e) = self.map(page, flags) {
+                for rolled_back in pages.clone().take(mapped) {
+                    let _ = self.unmap(rolled_back);
+                }
+                return Err(e);
+            }
+        }
+        Ok(MapperFlushRange { range: pages })
+    }
+
+    /// Unmaps a contiguous range of pages, reusing the already-locked frame
+    /// allocator for every page instead of re-locking it per call.
+    ///
+    /// If a page in the middle of the range fails to unmap, the pages
+    /// before it are already unmapped and stay that way — unlike a failed
+    /// [`map_range`](Self::map_range), leaving a range partially unmapped
+    /// is safe, since no stale or aliased mapping is left behind.
+    pub fn unmap_range(&mut self, pages: PageRange<Size4KB>) -> Result<MapperFlushRange, UnmapError> {
+        for (unmapped, page) in pages.clone().enumerate() {
+            if let Err(e) = self.unmap(page) {
+                for done in pages.clone().take(unmapped) {
+                    tlb::flush(done.start_address());
+                }
+                return Err(e);
+            }
+        }
+        Ok(MapperFlushRange { range: pages })
+    }
+}
+
+/// A deferred TLB flush for a range of pages mapped or unmapped by
+/// [`LockedPageMapper::map_range`]/[`unmap_range`](LockedPageMapper::unmap_range).
+///
+/// Must be consumed with [`flush_all`](Self::flush_all) or
+/// [`flush_range`](Self::flush_range) once the mapping is visible to other
+/// CPUs is no longer required immediately.
+#[must_use = "page table changes are not flushed from the TLB until this is consumed"]
+pub struct MapperFlushRange {
+    range: PageRange<Size4KB>,
+}
+
+impl MapperFlushRange {
+    /// Above this many pages, reloading CR3 is cheaper than sweeping
+    /// `invlpg` over the whole range.
+    const FULL_FLUSH_THRESHOLD: u64 = 64;
+
+    /// Flushes every page in the range, picking a single `invlpg` sweep or
+    /// a full TLB reload depending on how large the range is.
+    pub fn flush_all(self) {
+        if self.range.clone().count() as u64 > Self::FULL_FLUSH_THRESHOLD {
+            tlb::flush_all();
+        } else {
+            for page in self.range {
+                tlb::flush(page.start_address());
+            }
+        }
+    }
+
+    /// Flushes only `sub_range`, which should be contained within the range
+    /// this token was created for.
+    pub fn flush_range(self, sub_range: PageRange<Size4KB>) {
+        for page in sub_range {
+            tlb::flush(page.start_address());
+        }
+    }
+}
+
+/// The reason a [`LockedPageMapper::swap`] could not be performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapPageError {
+    /// The page is not present in the page table, so there's no frame to swap.
+    NotMapped(Page<Size4KB>),
+    /// The page's P1 entry is present but does not yield a [`Size4KB`]
+    /// frame, e.g. because its `HUGE_PAGE` bit is set.
+    InvalidFrameAddress(PhysAddr),
+}
+
+/// A deferred TLB flush for the two virtual addresses exchanged by
+/// [`LockedPageMapper::swap`]. Both pages are invalidated once this is
+/// consumed.
+#[must_use = "page table changes are not flushed from the TLB until this is consumed"]
+pub struct DoubleMapperFlush<S: PageSize> {
+    x: Page<S>,
+    y: Page<S>,
+}
+
+impl<S: PageSize> DoubleMapperFlush<S> {
+    pub fn flush(self) {
+        tlb::flush(self.x.start_address());
+        tlb::flush(self.y.start_address());
+    }
 }